@@ -7,6 +7,11 @@ pub trait Upstream {
     const NAME: &'static str;
     const EDITS_USE_BYTE_OFFSETS: bool = false;
 
+    /// Whether the backend can store rich-text formatting spans. Backends
+    /// that only model plain text (e.g. position-only CRDTs) leave this
+    /// `false` and opt out of the [`formatting`](crate::formatting) group.
+    const SUPPORTS_MARKS: bool = false;
+
     fn from_str(s: &str) -> Self;
 
     fn insert(&mut self, at_offset: usize, text: &str);
@@ -18,6 +23,32 @@ pub trait Upstream {
     /// [`EDITS_USE_BYTE_OFFSETS`](Self::EDITS_USE_BYTE_OFFSETS).
     fn len(&self) -> usize;
 
+    /// Annotates `range` with the `key`/`value` formatting pair (e.g.
+    /// `"bold"`/`"true"` or `"link"`/`"https://…"`). Automerge marks expand on
+    /// both ends, so text inserted at either boundary inherits the annotation;
+    /// Yrs formatting carries over to text inserted at the end of a span but
+    /// not to text inserted at its start.
+    ///
+    /// Only called when [`SUPPORTS_MARKS`](Self::SUPPORTS_MARKS) is `true`.
+    fn mark(&mut self, _range: Range<usize>, _key: &str, _value: &str) {
+        unimplemented!()
+    }
+
+    /// Removes the `key` annotation over `range`, the inverse of
+    /// [`mark`](Self::mark).
+    ///
+    /// Only called when [`SUPPORTS_MARKS`](Self::SUPPORTS_MARKS) is `true`.
+    fn unmark(&mut self, _range: Range<usize>, _key: &str) {
+        unimplemented!()
+    }
+
+    /// The number of distinct formatting spans currently stored. Used by the
+    /// benchmark harness to assert that marks survive boundary insertions
+    /// rather than being silently dropped.
+    fn num_marks(&self) -> usize {
+        0
+    }
+
     #[inline(always)]
     fn replace(&mut self, between_offsets: Range<usize>, text: &str) {
         let Range { start, end } = between_offsets;
@@ -36,6 +67,7 @@ pub trait Upstream {
 pub struct Automerge {
     doc: automerge::AutoCommit,
     text: Text,
+    obj: automerge::ObjId,
 }
 
 #[derive(Debug, Clone, autosurgeon::Reconcile, autosurgeon::Hydrate)]
@@ -45,13 +77,20 @@ struct Text {
 
 impl Upstream for Automerge {
     const NAME: &'static str = "automerge";
+    const SUPPORTS_MARKS: bool = true;
 
     #[inline(always)]
     fn from_str(s: &str) -> Self {
+        use automerge::ReadDoc;
         let mut doc = automerge::AutoCommit::new();
         let text = self::Text { text: s.into() };
         autosurgeon::reconcile(&mut doc, &text).unwrap();
-        Self { doc, text }
+        let obj = doc
+            .get(automerge::ROOT, "text")
+            .unwrap()
+            .expect("reconcile creates the `text` object")
+            .1;
+        Self { doc, text, obj }
     }
 
     #[inline(always)]
@@ -71,9 +110,36 @@ impl Upstream for Automerge {
         autosurgeon::reconcile(&mut self.doc, &self.text).unwrap();
     }
 
+    #[inline(always)]
+    fn mark(&mut self, range: Range<usize>, key: &str, value: &str) {
+        use automerge::marks::{ExpandMark, Mark};
+        use automerge::transaction::Transactable;
+        let mark = Mark::new(key.to_string(), value, range.start, range.end);
+        self.doc.mark(&self.obj, mark, ExpandMark::Both).unwrap();
+    }
+
+    #[inline(always)]
+    fn unmark(&mut self, range: Range<usize>, key: &str) {
+        use automerge::marks::ExpandMark;
+        use automerge::transaction::Transactable;
+        self.doc
+            .unmark(&self.obj, key, range.start, range.end, ExpandMark::Both)
+            .unwrap();
+    }
+
+    #[inline(always)]
+    fn num_marks(&self) -> usize {
+        use automerge::ReadDoc;
+        self.doc.marks(&self.obj).unwrap().len()
+    }
+
     #[inline(always)]
     fn len(&self) -> usize {
-        self.text.text.as_str().len()
+        // Read the document rather than the cached `text`, so the length is
+        // correct on the downstream path where edits arrive via
+        // `load_incremental` and never touch `self.text`.
+        use automerge::ReadDoc;
+        self.doc.text(&self.obj).unwrap().chars().count()
     }
 }
 
@@ -111,6 +177,10 @@ pub struct Dt {
 
 impl Upstream for Dt {
     const NAME: &'static str = "diamond-types";
+    // `SUPPORTS_MARKS` stays at its `false` default on purpose: diamond-types'
+    // `list::OpLog` has no formatting-mark API, so the backend is deliberately
+    // left out of the `formatting` group rather than forgotten. Same for
+    // `cola`, which only tracks positions and stores no text to annotate.
 
     #[inline(always)]
     fn from_str(s: &str) -> Self {
@@ -145,6 +215,7 @@ pub struct Yrs {
 impl Upstream for Yrs {
     const NAME: &'static str = "yrs";
     const EDITS_USE_BYTE_OFFSETS: bool = true;
+    const SUPPORTS_MARKS: bool = true;
 
     #[inline(always)]
     fn from_str(s: &str) -> Self {
@@ -174,6 +245,38 @@ impl Upstream for Yrs {
             .remove_range(&mut txn, range.start as u32, len as u32);
     }
 
+    #[inline(always)]
+    fn mark(&mut self, range: Range<usize>, key: &str, value: &str) {
+        use yrs::Text;
+        let len = range.end - range.start;
+        let attrs = yrs::types::Attrs::from([(key.into(), value.into())]);
+        let mut txn = self.doc.transact_mut();
+        self.text
+            .format(&mut txn, range.start as u32, len as u32, attrs);
+    }
+
+    #[inline(always)]
+    fn unmark(&mut self, range: Range<usize>, key: &str) {
+        use yrs::Text;
+        let len = range.end - range.start;
+        let attrs = yrs::types::Attrs::from([(key.into(), yrs::Any::Null)]);
+        let mut txn = self.doc.transact_mut();
+        self.text
+            .format(&mut txn, range.start as u32, len as u32, attrs);
+    }
+
+    #[inline(always)]
+    fn num_marks(&self) -> usize {
+        use yrs::types::text::YChange;
+        use yrs::Text;
+        let txn = self.doc.transact();
+        self.text
+            .diff(&txn, YChange::identity)
+            .into_iter()
+            .filter(|diff| diff.attributes.as_ref().is_some_and(|a| !a.is_empty()))
+            .count()
+    }
+
     #[inline(always)]
     fn len(&self) -> usize {
         use yrs::Text;
@@ -182,12 +285,70 @@ impl Upstream for Yrs {
     }
 }
 
+/// A single touched region reported when applying a remote update, as an
+/// editor would need to refresh its UI. Offsets are in the backend's own unit
+/// (the same one [`Upstream::len`] reports).
+pub enum Patch {
+    Insert(Range<usize>),
+    Delete(Range<usize>),
+}
+
+impl Patch {
+    /// The signed change in document length this patch represents.
+    fn delta(&self) -> isize {
+        match self {
+            Patch::Insert(range) => (range.end - range.start) as isize,
+            Patch::Delete(range) => -((range.end - range.start) as isize),
+        }
+    }
+}
+
+/// Derives insert/delete patches from two materializations of the document by
+/// trimming the common prefix and suffix and reporting the differing middle.
+fn diff_patches(before: &str, after: &str) -> Vec<Patch> {
+    let before: Vec<char> = before.chars().collect();
+    let after: Vec<char> = after.chars().collect();
+
+    let mut prefix = 0;
+    while prefix < before.len() && prefix < after.len() && before[prefix] == after[prefix] {
+        prefix += 1;
+    }
+
+    let mut suffix = 0;
+    while suffix < before.len() - prefix
+        && suffix < after.len() - prefix
+        && before[before.len() - 1 - suffix] == after[after.len() - 1 - suffix]
+    {
+        suffix += 1;
+    }
+
+    let mut patches = Vec::new();
+    let deleted_end = before.len() - suffix;
+    if deleted_end > prefix {
+        patches.push(Patch::Delete(prefix..deleted_end));
+    }
+    let inserted_end = after.len() - suffix;
+    if inserted_end > prefix {
+        patches.push(Patch::Insert(prefix..inserted_end));
+    }
+    patches
+}
+
 pub trait Downstream: Upstream + Clone {
     type Update;
 
     fn upstream_updates(trace: &crdt_testdata::TestData) -> (Self, Vec<Self::Update>);
 
     fn apply_update(&mut self, update: &Self::Update);
+
+    /// The serialized size, in bytes, of a single update as it would travel
+    /// over the wire. Summed across a trace it gives the network cost of the
+    /// incremental diff stream, the dimension that matters for real sync.
+    fn update_len(update: &Self::Update) -> usize;
+
+    /// Applies `update` and returns the ranges it touched, so callers can
+    /// refresh just the affected region rather than re-rendering everything.
+    fn apply_update_observed(&mut self, update: &Self::Update) -> Vec<Patch>;
 }
 
 impl Downstream for Dt {
@@ -222,17 +383,83 @@ impl Downstream for Dt {
     fn apply_update(&mut self, update: &Vec<u8>) {
         let _ = self.oplog.decode_and_add(update.as_slice());
     }
+
+    fn update_len(update: &Vec<u8>) -> usize {
+        update.len()
+    }
+
+    fn apply_update_observed(&mut self, update: &Vec<u8>) -> Vec<Patch> {
+        // diamond-types has no change observer, so diff the tip branch before
+        // and after integrating the update.
+        let before = self.oplog.checkout_tip().content().to_string();
+        let _ = self.oplog.decode_and_add(update.as_slice());
+        let after = self.oplog.checkout_tip().content().to_string();
+        diff_patches(&before, &after)
+    }
 }
 
 impl Downstream for Automerge {
-    type Update = Self;
+    type Update = Vec<u8>;
 
     fn upstream_updates(trace: &crdt_testdata::TestData) -> (Self, Vec<Self::Update>) {
-        todo!();
+        let mut upstream = Self::from_str(&trace.start_content);
+
+        // Flush the initial reconcile of `start_content` so it isn't folded
+        // into the first "incremental" update and inflate its byte size; every
+        // pushed bundle is then genuinely one edit's worth of changes.
+        let _ = upstream.doc.save_incremental();
+
+        // Seed the downstream from the upstream's initial state by cloning it.
+        // The clone shares the same actor and text object id, so the
+        // incremental bundles below — which reference the upstream object —
+        // splice into the *same* object on apply, and `len()` (which reads that
+        // object) reaches `end_content` instead of being stuck at the start.
+        let downstream = upstream.clone();
+
+        let mut updates = Vec::new();
+
+        for txn in &trace.txns {
+            for TestPatch(pos, del, ins) in &txn.patches {
+                upstream.replace(*pos..*pos + del, ins);
+                // Everything committed since the previous call: the per-edit
+                // incremental change bundle.
+                let bundle = upstream.doc.save_incremental();
+                if !bundle.is_empty() {
+                    updates.push(bundle);
+                }
+            }
+        }
+
+        (downstream, updates)
+    }
+
+    fn apply_update(&mut self, update: &Vec<u8>) {
+        let _ = self.doc.load_incremental(update);
+    }
+
+    fn update_len(update: &Vec<u8>) -> usize {
+        update.len()
     }
 
-    fn apply_update(&mut self, other: &Self::Update) {
-        let _ = self.doc.merge(&mut (other.doc.clone()));
+    fn apply_update_observed(&mut self, update: &Vec<u8>) -> Vec<Patch> {
+        use automerge::{Patch as AmPatch, PatchAction};
+        let _ = self.doc.load_incremental(update);
+        // `diff_incremental` hands back the patches emitted since the last
+        // call; keep the text splices/deletes and drop anything else.
+        self.doc
+            .diff_incremental()
+            .into_iter()
+            .filter_map(|AmPatch { action, .. }| match action {
+                PatchAction::SpliceText { index, value, .. } => {
+                    let len = value.make_string().chars().count();
+                    Some(Patch::Insert(index..index + len))
+                }
+                PatchAction::DeleteSeq { index, length } => {
+                    Some(Patch::Delete(index..index + length))
+                }
+                _ => None,
+            })
+            .collect()
     }
 }
 
@@ -263,7 +490,147 @@ impl Downstream for Yrs {
 
     #[inline(always)]
     fn apply_update(&mut self, update: &Self::Update) {
-        todo!();
-        // self.doc.transact_mut().apply_update(update);
+        let _ = self.doc.transact_mut().apply_update(update.clone());
+    }
+
+    fn update_len(update: &Self::Update) -> usize {
+        use yrs::updates::encoder::Encode;
+        update.encode_v1().len()
+    }
+
+    fn apply_update_observed(&mut self, update: &Self::Update) -> Vec<Patch> {
+        use std::cell::RefCell;
+        use std::rc::Rc;
+        use yrs::types::Delta;
+        use yrs::{Observable, Text};
+
+        // Walk the text delta the observer hands us, turning retain offsets
+        // and insert/delete runs into absolute ranges.
+        let patches = Rc::new(RefCell::new(Vec::new()));
+        let subscription = {
+            let patches = Rc::clone(&patches);
+            self.text.observe(move |txn, event| {
+                let mut index = 0usize;
+                for delta in event.delta(txn) {
+                    match delta {
+                        Delta::Retain(len, _) => index += *len as usize,
+                        Delta::Inserted(value, _) => {
+                            let len = value.to_string(txn).len();
+                            patches.borrow_mut().push(Patch::Insert(index..index + len));
+                            index += len;
+                        }
+                        Delta::Deleted(len) => {
+                            let len = *len as usize;
+                            patches.borrow_mut().push(Patch::Delete(index..index + len));
+                        }
+                    }
+                }
+            })
+        };
+
+        self.doc.transact_mut().apply_update(update.clone());
+        drop(subscription);
+
+        Rc::try_unwrap(patches).unwrap().into_inner()
+    }
+}
+
+/// Reconstructing per-character authorship ("who wrote this") from a CRDT's
+/// retained operation log.
+pub trait Attribution: Downstream {
+    /// Identifier for a simulated author.
+    type AgentId: Clone + PartialEq;
+
+    /// Builds a document by replaying `trace`, round-robining the patches
+    /// across a handful of simulated authors so the final state has a mix of
+    /// origins to attribute.
+    fn attributed(trace: &crdt_testdata::TestData) -> Self;
+
+    /// Walks the final state left-to-right and coalesces consecutive
+    /// characters sharing an origin into a single range, so the returned map
+    /// is proportional to the number of authorship runs rather than the
+    /// document length.
+    fn attribution(&self) -> Vec<(Range<usize>, Self::AgentId)>;
+}
+
+impl Attribution for Dt {
+    type AgentId = u32;
+
+    fn attributed(trace: &crdt_testdata::TestData) -> Self {
+        let mut oplog = diamond_types::list::OpLog::new();
+        let agents = [
+            oplog.get_or_create_agent_id("author-0"),
+            oplog.get_or_create_agent_id("author-1"),
+            oplog.get_or_create_agent_id("author-2"),
+        ];
+
+        let mut time = 0;
+
+        if !trace.start_content.is_empty() {
+            time = oplog.add_insert(agents[0], 0, &trace.start_content);
+        }
+
+        let mut n = 0usize;
+        for txn in &trace.txns {
+            for TestPatch(pos, del, ins) in &txn.patches {
+                let agent = agents[n % agents.len()];
+                n += 1;
+
+                if *del > 0 {
+                    time = oplog.add_delete_without_content(agent, *pos..*pos + del);
+                }
+
+                if !ins.is_empty() {
+                    time = oplog.add_insert(agent, *pos, ins);
+                }
+            }
+        }
+
+        Self {
+            oplog,
+            agent: agents[0],
+            time,
+        }
+    }
+
+    fn attribution(&self) -> Vec<(Range<usize>, Self::AgentId)> {
+        use diamond_types::list::operation::OpKind;
+
+        // Reconstruct per-character authorship straight from the op log: replay
+        // every operation in causal order against a buffer of origin agents,
+        // looking each inserted character's author up via the causal graph
+        // (`time_to_crdt_id`), then coalesce equal-agent runs. This is the work
+        // a real "who wrote this" query does, rather than reading precomputed
+        // state.
+        let mut origins: Vec<u32> = Vec::new();
+        let mut time = 0usize;
+
+        for op in self.oplog.iter() {
+            let len = op.len();
+            let start = op.loc.span.start;
+
+            match op.kind {
+                OpKind::Ins => {
+                    // One op-log run can hold consecutive edits by different
+                    // agents, so the author is resolved per character.
+                    let agents = (time..time + len).map(|t| self.oplog.time_to_crdt_id(t).agent);
+                    origins.splice(start..start, agents);
+                }
+                OpKind::Del => {
+                    origins.drain(start..op.loc.span.end);
+                }
+            }
+
+            time += len;
+        }
+
+        let mut map: Vec<(Range<usize>, u32)> = Vec::new();
+        for (pos, &agent) in origins.iter().enumerate() {
+            match map.last_mut() {
+                Some((range, a)) if *a == agent => range.end = pos + 1,
+                _ => map.push((pos..pos + 1, agent)),
+            }
+        }
+        map
     }
 }